@@ -1,16 +1,72 @@
 use core::cmp::Ordering;
 use smallvec::SmallVec;
 
-/// Stores a sorted list of indices with quick implementation for union, difference, intersection.
-#[derive(Debug, Clone, Eq, PartialEq)]
-pub struct SortedVecSet<const N: usize>(SmallVec<[usize; N]>);
+/// Number of bits held in a single word of the dense bitset representation.
+const BITS_PER_WORD: usize = u64::BITS as usize;
+
+/// Below this average gap between consecutive indices, a set is considered dense enough
+/// to switch from the sparse to the bitset representation.
+const DENSITY_THRESHOLD: usize = BITS_PER_WORD;
+
+/// Minimum number of elements before a sparse set is even considered for densifying.
+/// Without this, a tiny set of small indices (e.g. `{1, 2, 3}`) would pass the density
+/// ratio check and be converted to a bitset.
+const MIN_DENSE_LEN: usize = BITS_PER_WORD;
+
+/// A sorted, duplicate-free list of indices (`Sparse`), or a bitset where bit `i` of word
+/// `i / 64` means index `i` is present (`Dense`). `Dense` never has a trailing all-zero word.
+#[derive(Debug, Clone)]
+enum Repr<const N: usize> {
+    Sparse(SmallVec<[usize; N]>),
+    Dense(Vec<u64>),
+}
+
+/// Stores a sorted list of indices with quick implementations for union, difference,
+/// and intersection. Switches between a sparse `SmallVec` and a dense bitset depending
+/// on how densely packed the stored indices are.
+#[derive(Debug, Clone)]
+pub struct SortedVecSet<const N: usize>(Repr<N>);
+
+// Equality compares logical contents (via the sorted iterator), not the backing
+// representation: a `Dense` set shrunk down and a `Sparse` set built from the same
+// elements must compare equal even though their `Repr` variants differ.
+impl<const N: usize> PartialEq for SortedVecSet<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<const N: usize> Eq for SortedVecSet<N> {}
 
 impl<const N: usize> IntoIterator for SortedVecSet<N> {
     type Item = usize;
-    type IntoIter = <SmallVec<[usize; N]> as IntoIterator>::IntoIter;
+    type IntoIter = IndicesIntoIter<N>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.0.into_iter()
+        match self.0 {
+            Repr::Sparse(v) => IndicesIntoIter::Sparse(v.into_iter()),
+            Repr::Dense(words) => {
+                let indices: Vec<usize> = DenseIter::new(&words).collect();
+                IndicesIntoIter::Dense(indices.into_iter())
+            }
+        }
+    }
+}
+
+/// Owned iterator produced by [`SortedVecSet::into_iter`].
+pub enum IndicesIntoIter<const N: usize> {
+    Sparse(smallvec::IntoIter<[usize; N]>),
+    Dense(std::vec::IntoIter<usize>),
+}
+
+impl<const N: usize> Iterator for IndicesIntoIter<N> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        match self {
+            Self::Sparse(it) => it.next(),
+            Self::Dense(it) => it.next(),
+        }
     }
 }
 
@@ -23,7 +79,7 @@ impl<const N: usize> Default for SortedVecSet<N> {
 impl<const N: usize> SortedVecSet<N> {
     /// Construct an empty vector
     pub(crate) const fn new() -> Self {
-        Self(SmallVec::new_const())
+        Self(Repr::Sparse(SmallVec::new_const()))
     }
 
     /// Construct a new `SortedSmallVec` from a `Vec<usize>`.
@@ -32,122 +88,243 @@ impl<const N: usize> SortedVecSet<N> {
     /// Duplicates are removed.
     #[allow(dead_code)]
     pub(crate) fn from_vec(vec: Vec<usize>) -> Self {
-        let mut sorted_vec = Self(SmallVec::with_capacity(vec.len()));
+        let mut sorted_vec = Self::new();
         for value in vec {
             sorted_vec.insert(value);
         }
         sorted_vec
     }
 
-    pub(crate) fn iter(&self) -> impl Iterator<Item = usize> + '_ {
-        self.0.iter().copied()
+    pub(crate) fn iter(&self) -> IndicesIter<'_> {
+        match &self.0 {
+            Repr::Sparse(v) => IndicesIter::Sparse(v.iter()),
+            Repr::Dense(words) => IndicesIter::Dense(DenseIter::new(words)),
+        }
     }
 
     /// Insert the value if it's not already present in the vector.
     /// Maintains a sorted order.
     pub(crate) fn insert(&mut self, index: usize) {
-        match self.0.binary_search(&index) {
-            // element already present in the vector
-            Ok(_) => {}
-            Err(pos) => {
-                self.0.insert(pos, index);
+        match &mut self.0 {
+            Repr::Sparse(v) => {
+                match v.binary_search(&index) {
+                    // element already present in the vector
+                    Ok(_) => {}
+                    Err(pos) => v.insert(pos, index),
+                }
+                self.maybe_densify();
             }
+            Repr::Dense(words) => Self::dense_insert(words, index),
         }
     }
 
+    fn dense_insert(words: &mut Vec<u64>, index: usize) {
+        let word_idx = index / BITS_PER_WORD;
+        if word_idx >= words.len() {
+            words.resize(word_idx + 1, 0);
+        }
+        words[word_idx] |= 1u64 << (index % BITS_PER_WORD);
+    }
+
     /// Removes a value if it's present in the vector
     pub(crate) fn remove(&mut self, index: usize) {
-        if let Ok(pos) = self.0.binary_search(&index) {
-            self.0.remove(pos);
+        match &mut self.0 {
+            Repr::Sparse(v) => {
+                if let Ok(pos) = v.binary_search(&index) {
+                    v.remove(pos);
+                }
+            }
+            Repr::Dense(words) => {
+                if let Some(word) = words.get_mut(index / BITS_PER_WORD) {
+                    *word &= !(1u64 << (index % BITS_PER_WORD));
+                }
+                Self::trim_trailing_zero_words(words);
+            }
         }
     }
 
     /// Returns true if the vector contains the value.
     pub(crate) fn contains(&self, index: usize) -> bool {
-        self.0.binary_search(&index).is_ok()
+        match &self.0 {
+            Repr::Sparse(v) => v.binary_search(&index).is_ok(),
+            Repr::Dense(words) => match words.get(index / BITS_PER_WORD) {
+                Some(word) => word & (1u64 << (index % BITS_PER_WORD)) != 0,
+                None => false,
+            },
+        }
     }
 
     /// Returns true if the vector is empty.
     pub(crate) fn is_empty(&self) -> bool {
-        self.0.is_empty()
+        match &self.0 {
+            Repr::Sparse(v) => v.is_empty(),
+            Repr::Dense(words) => words.iter().all(|&word| word == 0),
+        }
     }
 
     /// Empties the contents of the vector
     pub(crate) fn clear(&mut self) {
-        self.0.clear();
+        match &mut self.0 {
+            Repr::Sparse(v) => v.clear(),
+            Repr::Dense(words) => words.clear(),
+        }
     }
 
     /// Returns the number of elements in the vector.
     pub(crate) fn len(&self) -> usize {
-        self.0.len()
+        match &self.0 {
+            Repr::Sparse(v) => v.len(),
+            Repr::Dense(words) => words.iter().map(|word| word.count_ones() as usize).sum(),
+        }
     }
 
     pub(crate) fn difference_with(&mut self, other: &Self) {
-        let mut j = 0;
-        self.0.retain(|current| {
-            // Advance past any smaller elements in other
-            while j < other.len() && other.0[j] < *current {
-                j += 1;
+        let self_dense = matches!(self.0, Repr::Dense(_));
+        let other_dense = matches!(other.0, Repr::Dense(_));
+
+        if self_dense && other_dense {
+            let (Repr::Dense(a), Repr::Dense(b)) = (&mut self.0, &other.0) else {
+                unreachable!()
+            };
+            for (word, &other_word) in a.iter_mut().zip(b.iter()) {
+                *word &= !other_word;
             }
-            // It's only in the difference if it's not in other,
-            // and this is the only place in other it could be
-            j < other.len() && !(other.0[j] == *current)
-        });
+            Self::trim_trailing_zero_words(a);
+        } else if !self_dense && !other_dense {
+            let (Repr::Sparse(a), Repr::Sparse(b)) = (&mut self.0, &other.0) else {
+                unreachable!()
+            };
+            let mut j = 0;
+            a.retain(|current| {
+                // Advance past any smaller elements in other
+                while j < b.len() && b[j] < *current {
+                    j += 1;
+                }
+                // It's only in the difference if it's not in other,
+                // and this is the only place in other it could be
+                !(j < b.len() && b[j] == *current)
+            });
+        } else if self.len() <= other.len() {
+            // Mixed representations, `self` is the smaller side: rebuild it from the
+            // indices that aren't present in `other`.
+            let result: SmallVec<[usize; N]> = self
+                .iter()
+                .filter(|&index| !other.contains(index))
+                .collect();
+            self.0 = Repr::Sparse(result);
+        } else {
+            // Mixed representations, `other` is the smaller side: test each of its
+            // indices against `self` and remove the ones found.
+            for index in other.iter() {
+                self.remove(index);
+            }
+        }
     }
 
     pub(crate) fn intersect_with(&mut self, other: &Self) {
-        let mut j = 0;
-        self.0.retain(|current| {
-            // Advance past any smaller elements in other
-            while j < other.len() && other.0[j] < *current {
-                j += 1;
+        let self_dense = matches!(self.0, Repr::Dense(_));
+        let other_dense = matches!(other.0, Repr::Dense(_));
+
+        if self_dense && other_dense {
+            let (Repr::Dense(a), Repr::Dense(b)) = (&mut self.0, &other.0) else {
+                unreachable!()
+            };
+            for (word, &other_word) in a.iter_mut().zip(b.iter()) {
+                *word &= other_word;
+            }
+            for word in a.iter_mut().skip(b.len()) {
+                *word = 0;
             }
-            // It's only in the intersection if it's in other,
-            // and this is the only place in other it could be
-            j < other.len() && other.0[j] == *current
-        });
+            Self::trim_trailing_zero_words(a);
+        } else if !self_dense && !other_dense {
+            let (Repr::Sparse(a), Repr::Sparse(b)) = (&mut self.0, &other.0) else {
+                unreachable!()
+            };
+            let mut j = 0;
+            a.retain(|current| {
+                // Advance past any smaller elements in other
+                while j < b.len() && b[j] < *current {
+                    j += 1;
+                }
+                // It's only in the intersection if it's in other,
+                // and this is the only place in other it could be
+                j < b.len() && b[j] == *current
+            });
+        } else {
+            // Mixed representations: iterate whichever side is smaller and test
+            // membership against the larger one, minimizing total membership checks.
+            let result: SmallVec<[usize; N]> = if self.len() <= other.len() {
+                self.iter().filter(|&index| other.contains(index)).collect()
+            } else {
+                other.iter().filter(|&index| self.contains(index)).collect()
+            };
+            self.0 = Repr::Sparse(result);
+        }
     }
 
     /// Adds all the elements from `other` into this vector. (skipping duplicates)
     pub(crate) fn union_with(&mut self, other: &Self) {
-        let mut i = 0;
-        let mut j = 0;
-        while i < self.len() && j < other.len() {
-            match self.0[i].cmp(&other.0[j]) {
-                Ordering::Less => i += 1,
-                Ordering::Greater => {
-                    self.0.insert(i, other.0[j]);
-                    j += 1;
-                }
-                Ordering::Equal => {
-                    i += 1;
-                    j += 1;
+        let self_dense = matches!(self.0, Repr::Dense(_));
+        let other_dense = matches!(other.0, Repr::Dense(_));
+
+        if self_dense && other_dense {
+            let (Repr::Dense(a), Repr::Dense(b)) = (&mut self.0, &other.0) else {
+                unreachable!()
+            };
+            if a.len() < b.len() {
+                a.resize(b.len(), 0);
+            }
+            for (word, &other_word) in a.iter_mut().zip(b.iter()) {
+                *word |= other_word;
+            }
+        } else if !self_dense && !other_dense {
+            let (Repr::Sparse(a), Repr::Sparse(b)) = (&mut self.0, &other.0) else {
+                unreachable!()
+            };
+            let mut i = 0;
+            let mut j = 0;
+            while i < a.len() && j < b.len() {
+                match a[i].cmp(&b[j]) {
+                    Ordering::Less => i += 1,
+                    Ordering::Greater => {
+                        a.insert(i, b[j]);
+                        j += 1;
+                    }
+                    Ordering::Equal => {
+                        i += 1;
+                        j += 1;
+                    }
                 }
             }
+            while j < b.len() {
+                a.push(b[j]);
+                j += 1;
+            }
+        } else {
+            // Mixed representations: insert every index of `other` through the
+            // existing `insert`, which already handles both backends and will
+            // densify `self` once it grows dense enough.
+            for index in other.iter() {
+                self.insert(index);
+            }
+            return;
         }
-        while j < other.len() {
-            self.0.push(other.0[j]);
-            j += 1;
-        }
+        self.maybe_densify();
     }
 
     /// Returns the elements that are in both `self` and `other`.
-    pub(crate) fn intersection<'a>(&'a self, other: &'a Self) -> Intersection<'a, N> {
+    pub(crate) fn intersection<'a>(&'a self, other: &'a Self) -> Intersection<'a> {
         Intersection {
-            this: self,
-            other,
-            i: 0,
-            j: 0,
+            this: self.iter().peekable(),
+            other: other.iter().peekable(),
         }
     }
 
     /// Return the elements that are in `self` but not in `other`.
-    pub(crate) fn difference<'a>(&'a self, other: &'a Self) -> Difference<'a, N> {
+    pub(crate) fn difference<'a>(&'a self, other: &'a Self) -> Difference<'a> {
         Difference {
-            this: self,
-            other,
-            i: 0,
-            j: 0,
+            this: self.iter().peekable(),
+            other: other.iter().peekable(),
         }
     }
 
@@ -160,83 +337,434 @@ impl<const N: usize> SortedVecSet<N> {
     pub(crate) fn is_subset(&self, other: &Self) -> bool {
         self.difference(other).next().is_none()
     }
+
+    /// Returns the contained indices in the half-open interval `[lo, hi)`, in sorted order.
+    #[allow(dead_code)]
+    pub(crate) fn range(&self, lo: usize, hi: usize) -> RangeIter<'_> {
+        if lo >= hi {
+            return RangeIter::Empty;
+        }
+        match &self.0 {
+            Repr::Sparse(v) => {
+                let start = v.partition_point(|&index| index < lo);
+                let end = v.partition_point(|&index| index < hi);
+                RangeIter::Sparse(v[start..end].iter())
+            }
+            Repr::Dense(words) => RangeIter::Dense(DenseRangeIter::new(words, lo, hi)),
+        }
+    }
+
+    /// Removes every index in the half-open interval `[lo, hi)` in one pass.
+    #[allow(dead_code)]
+    pub(crate) fn remove_range(&mut self, lo: usize, hi: usize) {
+        if lo >= hi {
+            return;
+        }
+        match &mut self.0 {
+            Repr::Sparse(v) => {
+                let start = v.partition_point(|&index| index < lo);
+                let end = v.partition_point(|&index| index < hi);
+                v.drain(start..end);
+            }
+            Repr::Dense(words) => {
+                let hi = hi.min(words.len() * BITS_PER_WORD);
+                if lo >= hi {
+                    return;
+                }
+                let start_word = lo / BITS_PER_WORD;
+                let end_word = (hi - 1) / BITS_PER_WORD;
+                for (word_idx, word) in words
+                    .iter_mut()
+                    .enumerate()
+                    .take(end_word + 1)
+                    .skip(start_word)
+                {
+                    let word_lo = word_idx * BITS_PER_WORD;
+                    let word_hi = word_lo + BITS_PER_WORD;
+                    let clear_lo = lo.max(word_lo) - word_lo;
+                    let clear_hi = hi.min(word_hi) - word_lo;
+                    let mask = if clear_hi == BITS_PER_WORD {
+                        !0u64 << clear_lo
+                    } else {
+                        (!0u64 << clear_lo) & !(!0u64 << clear_hi)
+                    };
+                    *word &= !mask;
+                }
+                Self::trim_trailing_zero_words(words);
+            }
+        }
+    }
+
+    /// Converts the sparse representation to the dense bitset one once the set holds
+    /// enough elements and they're packed closer together than [`BITS_PER_WORD`] apart.
+    fn maybe_densify(&mut self) {
+        let Repr::Sparse(v) = &self.0 else {
+            return;
+        };
+        if v.len() < MIN_DENSE_LEN {
+            return;
+        }
+        let (Some(&max_index), len) = (v.last(), v.len()) else {
+            return;
+        };
+        if max_index / len < DENSITY_THRESHOLD {
+            self.0 = Repr::Dense(Self::dense_words_from_sorted(v));
+        }
+    }
+
+    fn dense_words_from_sorted(indices: &[usize]) -> Vec<u64> {
+        let mut words = Vec::new();
+        for &index in indices {
+            Self::dense_insert(&mut words, index);
+        }
+        words
+    }
+
+    fn trim_trailing_zero_words(words: &mut Vec<u64>) {
+        while words.last() == Some(&0) {
+            words.pop();
+        }
+    }
+}
+
+/// Iterates the indices stored in a [`SortedVecSet`] in sorted order, regardless of
+/// which representation backs it.
+pub(crate) enum IndicesIter<'a> {
+    Sparse(core::slice::Iter<'a, usize>),
+    Dense(DenseIter<'a>),
+}
+
+impl<'a> Iterator for IndicesIter<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        match self {
+            Self::Sparse(it) => it.next().copied(),
+            Self::Dense(it) => it.next(),
+        }
+    }
+}
+
+/// Yields the set bits of a dense bitset in ascending order.
+pub(crate) struct DenseIter<'a> {
+    words: &'a [u64],
+    word_idx: usize,
+    cur: u64,
+}
+
+impl<'a> DenseIter<'a> {
+    fn new(words: &'a [u64]) -> Self {
+        Self {
+            words,
+            word_idx: 0,
+            cur: words.first().copied().unwrap_or(0),
+        }
+    }
+}
+
+impl<'a> Iterator for DenseIter<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        loop {
+            if self.cur != 0 {
+                let bit = self.cur.trailing_zeros() as usize;
+                self.cur &= self.cur - 1;
+                return Some(self.word_idx * BITS_PER_WORD + bit);
+            }
+            self.word_idx += 1;
+            self.cur = *self.words.get(self.word_idx)?;
+        }
+    }
+}
+
+/// Iterator produced by [`SortedVecSet::range`].
+pub(crate) enum RangeIter<'a> {
+    Empty,
+    Sparse(core::slice::Iter<'a, usize>),
+    Dense(DenseRangeIter<'a>),
+}
+
+impl<'a> Iterator for RangeIter<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        match self {
+            Self::Empty => None,
+            Self::Sparse(it) => it.next().copied(),
+            Self::Dense(it) => it.next(),
+        }
+    }
+}
+
+/// Like [`DenseIter`], but only yields set bits in `[lo, hi)`.
+pub(crate) struct DenseRangeIter<'a> {
+    words: &'a [u64],
+    word_idx: usize,
+    cur: u64,
+    hi: usize,
+}
+
+impl<'a> DenseRangeIter<'a> {
+    fn new(words: &'a [u64], lo: usize, hi: usize) -> Self {
+        let word_idx = lo / BITS_PER_WORD;
+        let cur = words.get(word_idx).copied().unwrap_or(0) & (!0u64 << (lo % BITS_PER_WORD));
+        Self {
+            words,
+            word_idx,
+            cur,
+            hi,
+        }
+    }
+}
+
+impl<'a> Iterator for DenseRangeIter<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        loop {
+            if self.cur != 0 {
+                let bit = self.cur.trailing_zeros() as usize;
+                let value = self.word_idx * BITS_PER_WORD + bit;
+                self.cur &= self.cur - 1;
+                if value >= self.hi {
+                    return None;
+                }
+                return Some(value);
+            }
+            self.word_idx += 1;
+            if self.word_idx * BITS_PER_WORD >= self.hi {
+                return None;
+            }
+            self.cur = *self.words.get(self.word_idx)?;
+        }
+    }
 }
 
 /// Intersection between `this` and `other` sorted vectors.
-pub(crate) struct Intersection<'a, const N: usize> {
-    this: &'a SortedVecSet<N>,
-    other: &'a SortedVecSet<N>,
-    i: usize,
-    j: usize,
+pub(crate) struct Intersection<'a> {
+    this: core::iter::Peekable<IndicesIter<'a>>,
+    other: core::iter::Peekable<IndicesIter<'a>>,
 }
 
-impl<'a, const N: usize> Iterator for Intersection<'a, N> {
+impl<'a> Iterator for Intersection<'a> {
     type Item = usize;
 
     // We assume that both self and other are sorted and contain no duplicates
     // Returns items in sorted order without duplicates
     fn next(&mut self) -> Option<Self::Item> {
-        while self.i < self.this.len() && self.j < self.other.len() {
-            let val_a = self.this.0[self.i];
-            let val_b = self.other.0[self.j];
-            if val_a == val_b {
-                self.i += 1;
-                self.j += 1;
-                return Some(val_a);
-            } else if val_a < val_b {
-                self.i += 1;
-            } else {
-                self.j += 1;
+        loop {
+            let &val_a = self.this.peek()?;
+            let &val_b = self.other.peek()?;
+            match val_a.cmp(&val_b) {
+                Ordering::Equal => {
+                    self.this.next();
+                    self.other.next();
+                    return Some(val_a);
+                }
+                Ordering::Less => {
+                    self.this.next();
+                }
+                Ordering::Greater => {
+                    self.other.next();
+                }
             }
         }
-        return None;
     }
 }
 
-impl<'a, const N: usize> From<Intersection<'a, N>> for SortedVecSet<N> {
-    fn from(intersection: Intersection<'a, N>) -> Self {
-        SortedVecSet(SmallVec::from_iter(intersection))
+impl<'a, const N: usize> From<Intersection<'a>> for SortedVecSet<N> {
+    fn from(intersection: Intersection<'a>) -> Self {
+        SortedVecSet(Repr::Sparse(SmallVec::from_iter(intersection)))
     }
 }
 
 /// Difference between `this` and `other` sorted vector sets. this - other.
-pub(crate) struct Difference<'a, const N: usize> {
-    this: &'a SortedVecSet<N>,
-    other: &'a SortedVecSet<N>,
-    i: usize,
-    j: usize,
+pub(crate) struct Difference<'a> {
+    this: core::iter::Peekable<IndicesIter<'a>>,
+    other: core::iter::Peekable<IndicesIter<'a>>,
 }
 
-impl<'a, const N: usize> Iterator for Difference<'a, N> {
+impl<'a> Iterator for Difference<'a> {
     type Item = usize;
 
     // We assume that both self and other are sorted and contain no duplicates
     // Returns items in sorted order without duplicates
     fn next(&mut self) -> Option<Self::Item> {
-        while self.i < self.this.len() && self.j < self.other.len() {
-            let val_a = self.this.0[self.i];
-            let val_b = self.other.0[self.j];
-            if val_a == val_b {
-                self.i += 1;
-                self.j += 1;
-            } else if val_a < val_b {
-                self.i += 1;
-                return Some(val_a);
-            } else {
-                self.j += 1;
+        loop {
+            match (self.this.peek(), self.other.peek()) {
+                (Some(&val_a), Some(&val_b)) => match val_a.cmp(&val_b) {
+                    Ordering::Equal => {
+                        self.this.next();
+                        self.other.next();
+                    }
+                    Ordering::Less => {
+                        self.this.next();
+                        return Some(val_a);
+                    }
+                    Ordering::Greater => {
+                        self.other.next();
+                    }
+                },
+                (Some(&val_a), None) => {
+                    self.this.next();
+                    return Some(val_a);
+                }
+                (None, _) => return None,
             }
         }
-        if self.i < self.this.len() {
-            let val_a = self.this.0[self.i];
-            self.i += 1;
-            return Some(val_a);
-        }
-        return None;
     }
 }
 
-impl<'a, const N: usize> From<Difference<'a, N>> for SortedVecSet<N> {
-    fn from(difference: Difference<'a, N>) -> Self {
-        SortedVecSet(SmallVec::from_iter(difference))
+impl<'a, const N: usize> From<Difference<'a>> for SortedVecSet<N> {
+    fn from(difference: Difference<'a>) -> Self {
+        SortedVecSet(Repr::Sparse(SmallVec::from_iter(difference)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(values: impl IntoIterator<Item = usize>) -> SortedVecSet<4> {
+        let mut set = SortedVecSet::new();
+        for value in values {
+            set.insert(value);
+        }
+        set
+    }
+
+    #[test]
+    fn stays_sparse_for_scattered_inserts() {
+        let s = set([5, 10_000_000]);
+        assert!(matches!(s.0, Repr::Sparse(_)));
+        assert_eq!(s.iter().collect::<Vec<_>>(), vec![5, 10_000_000]);
+    }
+
+    #[test]
+    fn densifies_once_packed_and_large_enough() {
+        let s = set(0..BITS_PER_WORD);
+        assert!(matches!(s.0, Repr::Dense(_)));
+        assert_eq!(
+            s.iter().collect::<Vec<_>>(),
+            (0..BITS_PER_WORD).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn dense_sets_with_same_indices_are_equal_after_removal() {
+        let mut a = set(0..BITS_PER_WORD);
+        let mut b = set(0..BITS_PER_WORD);
+        a.insert(200);
+        b.insert(200);
+        a.remove(200);
+        b.remove(200);
+        assert!(matches!(a.0, Repr::Dense(_)));
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn remove_trims_trailing_zero_words() {
+        let mut a = set(0..BITS_PER_WORD);
+        a.insert(200);
+        a.remove(200);
+        let Repr::Dense(words) = &a.0 else {
+            panic!("expected dense representation");
+        };
+        assert_ne!(words.last(), Some(&0));
+    }
+
+    #[test]
+    fn dense_and_sparse_sets_with_same_elements_are_equal() {
+        // `other` is dense too (64 elements: {1, 2, 3} plus a packed run in the second
+        // word, to clear `MIN_DENSE_LEN`), so this hits the dense/dense `intersect_with`
+        // branch, which trims trailing zero words but never re-checks density. The
+        // result stays `Dense` with only {1, 2, 3} left.
+        let mut shrunk = set(0..BITS_PER_WORD);
+        let mask = set([1, 2, 3]
+            .into_iter()
+            .chain(BITS_PER_WORD..BITS_PER_WORD + 61));
+        assert!(matches!(mask.0, Repr::Dense(_)));
+        shrunk.intersect_with(&mask);
+        assert!(matches!(shrunk.0, Repr::Dense(_)));
+
+        let direct = SortedVecSet::<4>::from_vec(vec![1, 2, 3]);
+        assert!(matches!(direct.0, Repr::Sparse(_)));
+
+        assert_eq!(shrunk, direct);
+    }
+
+    #[test]
+    fn range_empty_when_lo_at_or_above_hi() {
+        let s = set([1, 2, 3]);
+        assert_eq!(s.range(2, 2).collect::<Vec<_>>(), Vec::<usize>::new());
+        assert_eq!(s.range(3, 1).collect::<Vec<_>>(), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn range_clamps_to_bounds_beyond_stored_max() {
+        let sparse = set([1, 2, 3]);
+        assert_eq!(sparse.range(0, 1000).collect::<Vec<_>>(), vec![1, 2, 3]);
+
+        let dense = set(0..BITS_PER_WORD);
+        assert_eq!(
+            dense.range(BITS_PER_WORD - 1, 1000).collect::<Vec<_>>(),
+            vec![BITS_PER_WORD - 1]
+        );
+    }
+
+    #[test]
+    fn range_straddles_dense_word_boundary() {
+        let dense = set(0..BITS_PER_WORD * 2);
+        let expected: Vec<usize> = (BITS_PER_WORD - 2..BITS_PER_WORD + 2).collect();
+        assert_eq!(
+            dense
+                .range(BITS_PER_WORD - 2, BITS_PER_WORD + 2)
+                .collect::<Vec<_>>(),
+            expected
+        );
+    }
+
+    #[test]
+    fn range_agrees_between_sparse_and_dense() {
+        // Same logical contents, one forced sparse (too few elements to densify) and
+        // one forced dense (a packed run that clears both density thresholds).
+        let sparse = SortedVecSet::<8>::from_vec(vec![1, 2, 3]);
+        assert!(matches!(sparse.0, Repr::Sparse(_)));
+
+        let dense = set(1..BITS_PER_WORD + 3);
+        assert!(matches!(dense.0, Repr::Dense(_)));
+
+        assert_eq!(sparse.range(0, 3).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(dense.range(0, 3).collect::<Vec<_>>(), vec![1, 2]);
+    }
+
+    #[test]
+    fn remove_range_empty_when_lo_at_or_above_hi() {
+        let mut s = set([1, 2, 3]);
+        s.remove_range(2, 2);
+        assert_eq!(s.iter().collect::<Vec<_>>(), vec![1, 2, 3]);
+        s.remove_range(3, 1);
+        assert_eq!(s.iter().collect::<Vec<_>>(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn remove_range_clamps_beyond_stored_max() {
+        let mut s = set(0..BITS_PER_WORD);
+        s.remove_range(BITS_PER_WORD - 1, 1000);
+        assert_eq!(
+            s.iter().collect::<Vec<_>>(),
+            (0..BITS_PER_WORD - 1).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn remove_range_straddles_dense_word_boundary() {
+        let mut s = set(0..BITS_PER_WORD * 2);
+        s.remove_range(BITS_PER_WORD - 2, BITS_PER_WORD + 2);
+        let expected: Vec<usize> = (0..BITS_PER_WORD - 2)
+            .chain(BITS_PER_WORD + 2..BITS_PER_WORD * 2)
+            .collect();
+        assert_eq!(s.iter().collect::<Vec<_>>(), expected);
     }
 }
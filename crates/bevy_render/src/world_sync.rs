@@ -1,16 +1,19 @@
-use bevy_app::Plugin;
+use bevy_app::{App, Last, Plugin};
 use bevy_derive::{Deref, DerefMut};
 use bevy_ecs::{
-    component::Component,
+    component::{Component, ComponentId},
     entity::Entity,
     observer::Trigger,
-    query::With,
+    query::{Changed, With},
     reflect::ReflectComponent,
+    removal_detection::RemovedComponents,
     system::{Local, Query, ResMut, Resource, SystemState},
-    world::{Mut, OnAdd, OnRemove, World},
+    world::{EntityWorldMut, Mut, OnAdd, OnInsert, OnRemove, World},
 };
-use bevy_hierarchy::DespawnRecursiveExt;
+use bevy_hierarchy::{BuildWorldChildren, DespawnRecursiveExt, Parent};
 use bevy_reflect::Reflect;
+use bevy_utils::HashMap;
+use core::any::Any;
 
 /// Marker component that indicates that its entity needs to be Synchronized to the render world
 ///
@@ -49,6 +52,17 @@ pub(crate) enum EntityRecord {
     Added(Entity),
     // When an entity is despawned on the main world, notify the render world so that the corresponding entity can be despawned. This contains the render world entity.
     Removed(Entity),
+    // A component registered through `sync_component` was added to or changed on a main world entity. This contains the main world entity, the `ComponentId` of the changed component, and a clone of its new value.
+    ComponentUpdated {
+        main_entity: Entity,
+        component_id: ComponentId,
+        value: Box<dyn Any + Send + Sync>,
+    },
+    // A component registered through `sync_component` was removed from a main world entity. This contains the main world entity and the `ComponentId` of the removed component.
+    ComponentRemoved {
+        main_entity: Entity,
+        component_id: ComponentId,
+    },
 }
 
 // Entity Record in MainWorld pending to Sync
@@ -57,30 +71,168 @@ pub(crate) struct PendingSyncEntity {
     records: Vec<EntityRecord>,
 }
 
+// A main-world entity's Parent changed (Some(new_parent)) or was removed (None), pending
+// mirroring onto the synced render-world entities. Kept as main-world entities since either
+// side may not have a RenderEntity yet.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub(crate) struct PendingSyncedParent {
+    updates: Vec<(Entity, Option<Entity>)>,
+}
+
+// Boxed appliers and removers for every component type registered through
+// `SyncWorldAppExt::sync_component`, keyed by `ComponentId`.
+#[derive(Resource, Default)]
+pub(crate) struct SyncedComponents {
+    appliers: HashMap<
+        ComponentId,
+        Box<dyn Fn(&mut EntityWorldMut, Box<dyn Any + Send + Sync>) + Send + Sync>,
+    >,
+    removers: HashMap<ComponentId, Box<dyn Fn(&mut EntityWorldMut) + Send + Sync>>,
+}
+
+impl SyncedComponents {
+    fn register<C: Component + Clone>(&mut self, component_id: ComponentId) {
+        self.appliers.insert(
+            component_id,
+            Box::new(|entity, value| {
+                if let Ok(value) = value.downcast::<C>() {
+                    entity.insert(*value);
+                }
+            }),
+        );
+        self.removers.insert(
+            component_id,
+            Box::new(|entity| {
+                entity.remove::<C>();
+            }),
+        );
+    }
+}
+
 pub(crate) fn entity_sync_system(main_world: &mut World, render_world: &mut World) {
     main_world.resource_scope(|world, mut pending: Mut<PendingSyncEntity>| {
-        // TODO : batching record
+        let mut added = Vec::new();
+        let mut removed = Vec::new();
+        let mut component_updates = Vec::new();
+        let mut component_removals = Vec::new();
         for record in pending.drain(..) {
             match record {
-                EntityRecord::Added(e) => {
-                    if let Some(mut entity) = world.get_entity_mut(e) {
-                        match entity.entry::<RenderEntity>() {
-                            bevy_ecs::world::Entry::Occupied(_) => {}
-                            bevy_ecs::world::Entry::Vacant(entry) => {
-                                let id = render_world.spawn(MainEntity(e)).id();
-
-                                entry.insert(RenderEntity(id));
-                            }
-                        };
-                    }
+                EntityRecord::Added(e) => added.push(e),
+                EntityRecord::Removed(e) => removed.push(e),
+                EntityRecord::ComponentUpdated {
+                    main_entity,
+                    component_id,
+                    value,
+                } => component_updates.push((main_entity, component_id, value)),
+                EntityRecord::ComponentRemoved {
+                    main_entity,
+                    component_id,
+                } => component_removals.push((main_entity, component_id)),
+            }
+        }
+
+        // Only spawn for entities that don't already have a render entity.
+        added.retain(|&e| match world.get_entity_mut(e) {
+            Some(entity) => !entity.contains::<RenderEntity>(),
+            None => false,
+        });
+
+        if !added.is_empty() {
+            // Spawn all render-world entities in one batch instead of one at a time.
+            let render_entities: Vec<Entity> = render_world
+                .spawn_batch(added.iter().map(|&e| MainEntity(e)))
+                .collect();
+
+            for (&main_entity, &render_entity) in added.iter().zip(&render_entities) {
+                if let Some(mut entity) = world.get_entity_mut(main_entity) {
+                    entity.insert(RenderEntity(render_entity));
                 }
-                EntityRecord::Removed(e) => {
-                    if let Some(ec) = render_world.get_entity_mut(e) {
-                        ec.despawn_recursive();
+            }
+        }
+
+        // Despawned one at a time rather than batched like the spawn side above:
+        // `despawn_recursive` walks each entity's own hierarchy, and there's no
+        // bulk-despawn-recursive API to coalesce that into a single call.
+        for render_entity in removed {
+            if let Some(entity) = render_world.get_entity_mut(render_entity) {
+                entity.despawn_recursive();
+            }
+        }
+
+        let synced = world.resource::<SyncedComponents>();
+        for (main_entity, component_id, value) in component_updates {
+            let Some(render_entity) = world.get::<RenderEntity>(main_entity).map(RenderEntity::id)
+            else {
+                continue;
+            };
+            let Some(apply) = synced.appliers.get(&component_id) else {
+                continue;
+            };
+            if let Some(mut entity) = render_world.get_entity_mut(render_entity) {
+                apply(&mut entity, value);
+            }
+        }
+        for (main_entity, component_id) in component_removals {
+            let Some(render_entity) = world.get::<RenderEntity>(main_entity).map(RenderEntity::id)
+            else {
+                continue;
+            };
+            let Some(remove) = synced.removers.get(&component_id) else {
+                continue;
+            };
+            if let Some(mut entity) = render_world.get_entity_mut(render_entity) {
+                remove(&mut entity);
+            }
+        }
+    });
+
+    main_world.resource_scope(|world, mut pending: Mut<PendingSyncedParent>| {
+        // Entries without a resolvable RenderEntity yet are carried over to next frame,
+        // unless the main-world entity is already gone.
+        let mut unresolved = Vec::new();
+        for (main_child, main_parent) in pending.drain(..) {
+            if world.get_entity(main_child).is_none() {
+                continue;
+            }
+            let Some(render_child) = world.get::<RenderEntity>(main_child).map(RenderEntity::id)
+            else {
+                unresolved.push((main_child, main_parent));
+                continue;
+            };
+
+            match main_parent {
+                Some(main_parent) => {
+                    if world.get_entity(main_parent).is_none() {
+                        if let Some(mut render_child) = render_world.get_entity_mut(render_child) {
+                            render_child.remove_parent();
+                        }
+                        continue;
+                    }
+                    let Some(render_parent) =
+                        world.get::<RenderEntity>(main_parent).map(RenderEntity::id)
+                    else {
+                        unresolved.push((main_child, Some(main_parent)));
+                        continue;
+                    };
+                    // The render entity for `render_child` or `render_parent` may already be
+                    // despawned this frame (e.g. `SyncRenderWorld` was removed without clearing
+                    // the stale `RenderEntity`); drop the update rather than panic.
+                    if render_world.get_entity(render_parent).is_none() {
+                        continue;
+                    }
+                    let Some(mut render_child) = render_world.get_entity_mut(render_child) else {
+                        continue;
                     };
+                    render_child.set_parent(render_parent);
+                }
+                None => {
+                    if let Some(mut render_child) = render_world.get_entity_mut(render_child) {
+                        render_child.remove_parent();
+                    }
                 }
             }
         }
+        pending.extend(unresolved);
     });
 }
 
@@ -108,6 +260,8 @@ pub struct WorldSyncPlugin;
 impl Plugin for WorldSyncPlugin {
     fn build(&self, app: &mut bevy_app::App) {
         app.init_resource::<PendingSyncEntity>();
+        app.init_resource::<PendingSyncedParent>();
+        app.init_resource::<SyncedComponents>();
         app.observe(
             |trigger: Trigger<OnAdd, SyncRenderWorld>, mut pending: ResMut<PendingSyncEntity>| {
                 pending.push(EntityRecord::Added(trigger.entity()));
@@ -117,10 +271,208 @@ impl Plugin for WorldSyncPlugin {
             |trigger: Trigger<OnRemove, SyncRenderWorld>,
              mut pending: ResMut<PendingSyncEntity>,
              query: Query<&RenderEntity>| {
-                if let Ok(e) = query.get(trigger.entity()) {
+                let main_entity = trigger.entity();
+                // Cancel a still-pending `Added` record instead of emitting `Removed`.
+                let pending_len = pending.len();
+                pending.retain(
+                    |record| !matches!(record, EntityRecord::Added(e) if *e == main_entity),
+                );
+                if pending.len() != pending_len {
+                    return;
+                }
+                if let Ok(e) = query.get(main_entity) {
                     pending.push(EntityRecord::Removed(e.id()));
                 };
             },
         );
+        app.observe(
+            // `OnInsert`, not `OnAdd`: re-parenting overwrites an existing `Parent`
+            // without removing it first, so `OnAdd` would miss it.
+            |trigger: Trigger<OnInsert, Parent>,
+             mut pending: ResMut<PendingSyncedParent>,
+             query: Query<&Parent, With<SyncRenderWorld>>| {
+                if let Ok(parent) = query.get(trigger.entity()) {
+                    pending.push((trigger.entity(), Some(parent.get())));
+                }
+            },
+        );
+        app.observe(
+            |trigger: Trigger<OnRemove, Parent>,
+             mut pending: ResMut<PendingSyncedParent>,
+             query: Query<(), With<SyncRenderWorld>>| {
+                if query.get(trigger.entity()).is_ok() {
+                    pending.push((trigger.entity(), None));
+                }
+            },
+        );
+    }
+}
+
+/// Extension methods layered on [`WorldSyncPlugin`] for mirroring component data.
+pub trait SyncWorldAppExt {
+    /// Registers `C` so that it's synced onto the matching `RenderEntity` whenever it's
+    /// added, changed, or removed on a `SyncRenderWorld` entity.
+    fn sync_component<C: Component + Clone>(&mut self) -> &mut Self;
+}
+
+impl SyncWorldAppExt for App {
+    fn sync_component<C: Component + Clone>(&mut self) -> &mut Self {
+        let component_id = self.world_mut().register_component::<C>();
+        self.world_mut()
+            .resource_mut::<SyncedComponents>()
+            .register::<C>(component_id);
+        self.add_systems(Last, queue_synced_component::<C>(component_id));
+        self
+    }
+}
+
+/// Builds the per-frame system that watches `C` for changes on `SyncRenderWorld`
+/// entities and turns them into [`EntityRecord`]s.
+fn queue_synced_component<C: Component + Clone>(
+    component_id: ComponentId,
+) -> impl FnMut(
+    Query<(Entity, &C), (With<SyncRenderWorld>, Changed<C>)>,
+    RemovedComponents<C>,
+    ResMut<PendingSyncEntity>,
+) {
+    move |query, mut removed_components, mut pending| {
+        for (main_entity, value) in &query {
+            pending.push(EntityRecord::ComponentUpdated {
+                main_entity,
+                component_id,
+                value: Box::new(value.clone()),
+            });
+        }
+        for main_entity in removed_components.read() {
+            pending.push(EntityRecord::ComponentRemoved {
+                main_entity,
+                component_id,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_app::App;
+
+    fn sync(app: &mut App, render_world: &mut World) {
+        entity_sync_system(app.world_mut(), render_world);
+    }
+
+    #[test]
+    fn coalesced_add_and_remove_never_touches_render_world() {
+        let mut app = App::new();
+        app.add_plugins(WorldSyncPlugin);
+        let mut render_world = World::new();
+
+        // Spawned and despawned within the same tick: the `OnRemove` observer should
+        // cancel the still-pending `Added` record instead of queuing a `Removed` for an
+        // entity the render world never spawned.
+        let entity = app.world_mut().spawn(SyncRenderWorld).id();
+        app.world_mut().despawn(entity);
+
+        sync(&mut app, &mut render_world);
+
+        assert_eq!(render_world.entities().len(), 0);
+    }
+
+    #[test]
+    fn child_resolves_before_parent_is_carried_over() {
+        let mut app = App::new();
+        app.add_plugins(WorldSyncPlugin);
+        let mut render_world = World::new();
+
+        // The child is already synced, but the parent isn't (yet). Reparenting queues an
+        // update that can't be applied this frame, since the parent has no RenderEntity.
+        let main_child = app.world_mut().spawn(SyncRenderWorld).id();
+        let main_parent = app.world_mut().spawn_empty().id();
+        app.world_mut().entity_mut(main_child).set_parent(main_parent);
+
+        sync(&mut app, &mut render_world);
+
+        let render_child = app.world().get::<RenderEntity>(main_child).unwrap().id();
+        assert!(render_world.get::<bevy_hierarchy::Parent>(render_child).is_none());
+
+        // Once the parent is synced too, the carried-over update resolves.
+        app.world_mut().entity_mut(main_parent).insert(SyncRenderWorld);
+        sync(&mut app, &mut render_world);
+
+        let render_parent = app.world().get::<RenderEntity>(main_parent).unwrap().id();
+        assert_eq!(
+            render_world
+                .get::<bevy_hierarchy::Parent>(render_child)
+                .unwrap()
+                .get(),
+            render_parent
+        );
+    }
+
+    #[test]
+    fn despawn_race_does_not_panic() {
+        let mut app = App::new();
+        app.add_plugins(WorldSyncPlugin);
+        let mut render_world = World::new();
+
+        let main_parent = app.world_mut().spawn(SyncRenderWorld).id();
+        let main_child = app.world_mut().spawn(SyncRenderWorld).id();
+        let main_other_parent = app.world_mut().spawn(SyncRenderWorld).id();
+        app.world_mut().entity_mut(main_child).set_parent(main_parent);
+        sync(&mut app, &mut render_world);
+
+        // Reparent the child (queues a pending parent-sync update) and remove
+        // `SyncRenderWorld` from it in the same tick (queues a `Removed` for its stale
+        // render entity). The `Removed` is processed first and despawns the render
+        // entity; the parent-sync update must not then panic trying to use it.
+        app.world_mut()
+            .entity_mut(main_child)
+            .set_parent(main_other_parent);
+        app.world_mut().entity_mut(main_child).remove::<SyncRenderWorld>();
+
+        sync(&mut app, &mut render_world);
+
+        assert!(app.world().get::<RenderEntity>(main_child).is_some());
+    }
+
+    #[derive(Component, Clone, Debug, PartialEq)]
+    struct TestMirrored(u32);
+
+    #[test]
+    fn sync_component_mirrors_insert_update_and_remove() {
+        let mut app = App::new();
+        app.add_plugins(WorldSyncPlugin);
+        app.sync_component::<TestMirrored>();
+        let mut render_world = World::new();
+
+        let main_entity = app.world_mut().spawn(SyncRenderWorld).id();
+        sync(&mut app, &mut render_world);
+        let render_entity = app.world().get::<RenderEntity>(main_entity).unwrap().id();
+
+        app.world_mut()
+            .entity_mut(main_entity)
+            .insert(TestMirrored(1));
+        app.world_mut().run_schedule(Last);
+        sync(&mut app, &mut render_world);
+        assert_eq!(
+            render_world.get::<TestMirrored>(render_entity),
+            Some(&TestMirrored(1))
+        );
+
+        app.world_mut()
+            .get_mut::<TestMirrored>(main_entity)
+            .unwrap()
+            .0 = 2;
+        app.world_mut().run_schedule(Last);
+        sync(&mut app, &mut render_world);
+        assert_eq!(
+            render_world.get::<TestMirrored>(render_entity),
+            Some(&TestMirrored(2))
+        );
+
+        app.world_mut().entity_mut(main_entity).remove::<TestMirrored>();
+        app.world_mut().run_schedule(Last);
+        sync(&mut app, &mut render_world);
+        assert!(render_world.get::<TestMirrored>(render_entity).is_none());
     }
 }